@@ -1,26 +1,74 @@
 extern crate num;
 extern crate image;
 extern crate crossbeam;
+extern crate rand;
+extern crate rayon;
 
 use image::ColorType;
 use image::png::PNGEncoder;
 use num::Complex;
+use rand::Rng;
+use rayon::prelude::*;
 use std::fs::File;
 use std::io::Write;
 use std::str::FromStr;
 
-/// Determine if the Mandelbrot sequence escapes the finite attraction basin
-/// using limit as the iteration limit.
+/// The fractal family to render. Each variant only changes the per-iteration
+/// update rule; the escape test and iteration count are shared.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FractalKind {
+    Mandelbrot,
+    Multibrot3,
+    BurningShip
+}
+
+impl FromStr for FractalKind {
+    type Err = ();
+
+    /// Parse a fractal kind from its CLI name, e.g. "mandelbrot",
+    /// "multibrot3", or "burning_ship".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mandelbrot" => Ok(FractalKind::Mandelbrot),
+            "multibrot3" => Ok(FractalKind::Multibrot3),
+            "burning_ship" => Ok(FractalKind::BurningShip),
+            _ => Err(())
+        }
+    }
+}
+
+#[test]
+fn test_fractal_kind_from_str() {
+    assert_eq!(FractalKind::from_str("mandelbrot"), Ok(FractalKind::Mandelbrot));
+    assert_eq!(FractalKind::from_str("multibrot3"), Ok(FractalKind::Multibrot3));
+    assert_eq!(FractalKind::from_str("burning_ship"), Ok(FractalKind::BurningShip));
+    assert_eq!(FractalKind::from_str("nonsense"), Err(()));
+}
+
+/// Apply one iteration of `fractal`'s update rule to `z` given the constant `c`.
+fn fractal_step(z: Complex<f64>, c: Complex<f64>, fractal: FractalKind) -> Complex<f64> {
+    match fractal {
+        FractalKind::Mandelbrot => z * z + c,
+        FractalKind::Multibrot3 => z * z * z + c,
+        FractalKind::BurningShip => {
+            let z = Complex { re: z.re.abs(), im: z.im.abs() };
+            z * z + c
+        }
+    }
+}
+
+/// Determine if the sequence escapes the finite attraction basin using limit
+/// as the iteration limit, following `fractal`'s update rule.
 ///
 /// This function will compute the sequence f(c), f(f(c)), f(f(f(c))), etc.
 /// up to limit times and see if it escapes the attraction basin at 0.
 /// If it does, this function will return the number of iterations it took
 /// for the sequence to escape as Some(i).
 /// If it does not, this function will return None.
-fn escape_time(c: Complex<f64>, limit: u32) -> Option<u32> {
+fn escape_time(c: Complex<f64>, limit: u32, fractal: FractalKind) -> Option<u32> {
     let mut z = Complex { re: 0.0, im: 0.0 };
     for i in 0..limit {
-        z = z * z + c;
+        z = fractal_step(z, c, fractal);
         if z.norm_sqr() > 4.0 {
             return Some(i);
         }
@@ -53,7 +101,7 @@ fn test_parse_pair() {
     assert_eq!(parse_pair::<i32>("", ','), None);
     assert_eq!(parse_pair::<i32>("10,", ','), None);
     assert_eq!(parse_pair::<i32>(",10", ','), None);
-    assert_eq!(parse_pair::<f64>("10.0,20.0", ','), Some((10, 20)));
+    assert_eq!(parse_pair::<f64>("10.0,20.0", ','), Some((10.0, 20.0)));
     assert_eq!(parse_pair::<i32>("10,20xy", ','), None);
     assert_eq!(parse_pair::<f64>("0.5x", 'x'), None);
     assert_eq!(parse_pair::<f64>("0.5x1.5", 'x'), Some((0.5, 1.5)));
@@ -61,10 +109,7 @@ fn test_parse_pair() {
 
 /// Parse a pair of floats separated by a comma as a complex number.
 fn parse_complex(s: &str) -> Option<Complex<f64>> {
-    match parse_pair(s, ',') {
-        Some((re, im)) => Some(Complex { re, im }),
-        None => None
-    }
+    parse_pair(s, ',').map(|(re, im)| Complex { re, im })
 }
 
 #[test]
@@ -106,15 +151,123 @@ fn test_pixel_to_point() {
     );
 }
 
+/// A color palette that a smoothed escape value is mapped into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Palette {
+    Grayscale,
+    Gradient
+}
+
+impl FromStr for Palette {
+    type Err = ();
+
+    /// Parse a palette from its CLI name, e.g. "grayscale" or "gradient".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grayscale" => Ok(Palette::Grayscale),
+            "gradient" => Ok(Palette::Gradient),
+            _ => Err(())
+        }
+    }
+}
+
+#[test]
+fn test_palette_from_str() {
+    assert_eq!(Palette::from_str("grayscale"), Ok(Palette::Grayscale));
+    assert_eq!(Palette::from_str("gradient"), Ok(Palette::Gradient));
+    assert_eq!(Palette::from_str("nonsense"), Err(()));
+}
+
+/// Determine the smoothed (fractional) escape value for `c`, following
+/// `fractal`'s update rule up to `limit` iterations.
+///
+/// Coloring directly off the integer iteration count produces visible
+/// concentric bands at high zoom. Smoothing instead interpolates between
+/// iterations using the standard continuous escape-time estimate
+/// `mu = i + 1 - ln(ln(|z|)) / ln(2)`, computed a couple of iterations past
+/// the escape point to sharpen the estimate. Returns `None` if `c` never
+/// escapes within `limit` iterations.
+///
+/// The `ln(2)` divisor is the correct normalization for the degree-2
+/// Mandelbrot update rule; for `Multibrot3` the exact estimate would divide
+/// by `ln(3)` instead, so the smoothing is a good approximation there rather
+/// than the precise continuous count.
+fn smooth_escape_time(c: Complex<f64>, limit: u32, fractal: FractalKind) -> Option<f64> {
+    let mut z = Complex { re: 0.0, im: 0.0 };
+    for i in 0..limit {
+        z = fractal_step(z, c, fractal);
+        if z.norm_sqr() > 4.0 {
+            for _ in 0..2 {
+                z = fractal_step(z, c, fractal);
+            }
+            let modulus = z.norm_sqr().sqrt();
+            let mu = i as f64 + 1.0 - (modulus.ln()).ln() / std::f64::consts::LN_2;
+            return Some(mu);
+        }
+    }
+
+    None
+}
+
+#[test]
+fn test_smooth_escape_time() {
+    // The origin never escapes the Mandelbrot set.
+    let origin = Complex { re: 0.0, im: 0.0 };
+    assert_eq!(smooth_escape_time(origin, 255, FractalKind::Mandelbrot), None);
+
+    // A point far outside the set escapes almost immediately.
+    let mu = smooth_escape_time(Complex { re: 5.0, im: 5.0 }, 255, FractalKind::Mandelbrot);
+    assert!(mu.is_some());
+    assert!(mu.unwrap() < 2.0);
+}
+
+/// Map a smoothed escape value (as returned by `smooth_escape_time`) into an
+/// RGB pixel using `palette`. Points that never escape are always black.
+fn color_at(mu: Option<f64>, limit: u32, palette: Palette) -> [u8; 3] {
+    let mu = match mu {
+        None => return [0, 0, 0],
+        Some(mu) => (mu / limit as f64).clamp(0.0, 1.0)
+    };
+
+    match palette {
+        Palette::Grayscale => {
+            let v = 255 - (mu * 255.0) as u8;
+            [v, v, v]
+        },
+        Palette::Gradient => {
+            let r = (9.0 * (1.0 - mu) * mu * mu * mu * 255.0) as u8;
+            let g = (15.0 * (1.0 - mu) * (1.0 - mu) * mu * mu * 255.0) as u8;
+            let b = (8.5 * (1.0 - mu) * (1.0 - mu) * (1.0 - mu) * mu * 255.0) as u8;
+            [r, g, b]
+        }
+    }
+}
+
+#[test]
+fn test_color_at() {
+    assert_eq!(color_at(None, 255, Palette::Grayscale), [0, 0, 0]);
+    assert_eq!(color_at(None, 255, Palette::Gradient), [0, 0, 0]);
+
+    // A quick escape (small mu) should stay close to white in grayscale...
+    let quick = color_at(Some(1.0), 255, Palette::Grayscale);
+    assert!(quick[0] > 250 && quick[0] == quick[1] && quick[1] == quick[2]);
+
+    // ...and a value past the limit should clamp rather than wrap/overflow.
+    let clamped = color_at(Some(1000.0), 255, Palette::Grayscale);
+    assert_eq!(clamped, [0, 0, 0]);
+}
+
 /// Render a rectangle of the Mandelbrot set into a buffer of pixels.
 ///
 /// The `pixel_width` and `pixel_height` arguments give the width and height in pixels
-/// of the `pixels` buffer, which holds one grayscale pixel per byte. The `upper_left`
+/// of the `pixels` buffer, which holds one RGB pixel per three bytes. The `upper_left`
 /// and `lower_right` arguments specify points on the complex plane corresponding to the
-/// upper-left and lower-right corners of the pixel buffer.
+/// upper-left and lower-right corners of the pixel buffer. `limit` bounds the number of
+/// iterations per point, and `palette` selects how smoothed escape values are colored.
+#[allow(clippy::too_many_arguments)]
 fn render(
     pixels: &mut [u8], pixel_width: usize, pixel_height: usize, upper_left: Complex<f64>,
-    lower_right: Complex<f64>
+    lower_right: Complex<f64>, limit: u32, fractal: FractalKind, palette: Palette
 ) {
     assert!(pixels.len() == pixel_width * pixel_height * 3);
 
@@ -123,42 +276,223 @@ fn render(
             let point = pixel_to_point(
                 pixel_width, pixel_height, (column, row), upper_left, lower_right
             );
-            match escape_time(point, 255) {
-                None => {
-                    pixels[row * pixel_width * 3 + column * 3] = 0;
-                    pixels[row * pixel_width * 3 + column * 3 + 1] = 0;
-                    pixels[row * pixel_width * 3 + column * 3 + 2] = 0;
-                },
-                Some(count) => {
-                    pixels[row * pixel_width * 3 + column * 3] = 255 - count as u8;
-                    pixels[row * pixel_width * 3 + column * 3 + 1] =
-                        (column * 255 / pixel_width) as u8;
-                    pixels[row * pixel_width * 3 + column * 3 + 2] =
-                        (255 - column * 255 / pixel_width) as u8;
-                }
+            let mu = smooth_escape_time(point, limit, fractal);
+            let color = color_at(mu, limit, palette);
+            pixels[row * pixel_width * 3 + column * 3] = color[0];
+            pixels[row * pixel_width * 3 + column * 3 + 1] = color[1];
+            pixels[row * pixel_width * 3 + column * 3 + 2] = color[2];
+        }
+    }
+}
+
+/// Which of the two rendering pipelines to use.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RenderMode {
+    EscapeTime,
+    Buddhabrot
+}
+
+impl FromStr for RenderMode {
+    type Err = ();
+
+    /// Parse a render mode from its CLI name, e.g. "escape_time" or
+    /// "buddhabrot".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "escape_time" => Ok(RenderMode::EscapeTime),
+            "buddhabrot" => Ok(RenderMode::Buddhabrot),
+            _ => Err(())
+        }
+    }
+}
+
+/// The reverse of `pixel_to_point`: given a point on the complex plane and
+/// the same viewport used to render it, return the pixel it falls in, or
+/// `None` if the point lies outside the viewport.
+fn point_to_pixel(
+    pixel_width: usize, pixel_height: usize, point: Complex<f64>, upper_left: Complex<f64>,
+    lower_right: Complex<f64>
+) -> Option<(usize, usize)> {
+    let (width, height) = (
+        lower_right.re - upper_left.re,
+        upper_left.im - lower_right.im
+    );
+    let column = (point.re - upper_left.re) * pixel_width as f64 / width;
+    let row = (upper_left.im - point.im) * pixel_height as f64 / height;
+
+    if column < 0.0 || row < 0.0 || column >= pixel_width as f64 || row >= pixel_height as f64 {
+        return None;
+    }
+
+    Some((column as usize, row as usize))
+}
+
+#[test]
+fn test_point_to_pixel() {
+    let upper_left = Complex { re: -1.0, im: 1.0 };
+    let lower_right = Complex { re: 1.0, im: -1.0 };
+
+    // Round-trips with pixel_to_point for an interior pixel.
+    let point = pixel_to_point(100, 100, (25, 75), upper_left, lower_right);
+    assert_eq!(point_to_pixel(100, 100, point, upper_left, lower_right), Some((25, 75)));
+
+    // The last pixel in each dimension is the half-open upper bound, so the
+    // point mapping to it must still resolve to a valid pixel...
+    let last = pixel_to_point(100, 100, (99, 99), upper_left, lower_right);
+    assert_eq!(point_to_pixel(100, 100, last, upper_left, lower_right), Some((99, 99)));
+
+    // ...while a point outside the viewport on either side is rejected.
+    assert_eq!(
+        point_to_pixel(100, 100, Complex { re: -2.0, im: 0.0 }, upper_left, lower_right),
+        None
+    );
+    assert_eq!(
+        point_to_pixel(100, 100, Complex { re: 0.0, im: 2.0 }, upper_left, lower_right),
+        None
+    );
+    assert_eq!(
+        point_to_pixel(100, 100, lower_right, upper_left, lower_right),
+        None
+    );
+}
+
+/// How far outside the viewport (as a fraction of its width/height) to
+/// sample candidate Buddhabrot points from. Escaping trajectories often pass
+/// through the frame we're rendering without originating inside it.
+const BUDDHABROT_MARGIN: f64 = 0.25;
+
+/// A per-pixel hit-count accumulator used by the Buddhabrot renderer.
+struct HitGrid {
+    pixel_width: usize,
+    pixel_height: usize,
+    counts: Vec<u32>
+}
+
+impl HitGrid {
+    fn new(pixel_width: usize, pixel_height: usize) -> HitGrid {
+        HitGrid { pixel_width, pixel_height, counts: vec![0; pixel_width * pixel_height] }
+    }
+
+    fn hit(&mut self, column: usize, row: usize) {
+        self.counts[row * self.pixel_width + column] += 1;
+    }
+
+    /// Fold another grid's counts into this one, pixel by pixel.
+    fn merge(&mut self, other: &HitGrid) {
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += *other_count;
+        }
+    }
+
+    /// Normalize the accumulated hit counts into a grayscale RGB pixel
+    /// buffer, using a log scale so that the small number of very "hot"
+    /// pixels near the origin don't wash out the long, faint trajectory
+    /// tails that give the Buddhabrot its shape.
+    fn into_pixels(self) -> Vec<u8> {
+        let max_log = (self.counts.iter().cloned().max().unwrap_or(0) as f64 + 1.0).ln();
+
+        let mut pixels = vec![0; self.pixel_width * self.pixel_height * 3];
+        for (i, &count) in self.counts.iter().enumerate() {
+            let value = if max_log == 0.0 {
+                0
+            } else {
+                (((count as f64 + 1.0).ln() / max_log) * 255.0) as u8
             };
-            /*
-            pixels[row * pixel_width + column * 3] =
-                match escape_time(point, 255) {
-                    None => 0,
-                    Some(count) => 255 - count as u8
-                };
-            pixels[row * pixel_width + column * 3 + 1] = 0;
-            pixels[row * pixel_width + column * 3 + 2] = 0;
-            */
+            pixels[i * 3] = value;
+            pixels[i * 3 + 1] = value;
+            pixels[i * 3 + 2] = value;
+        }
+        pixels
+    }
+}
+
+/// Sample `samples` random points `c` from the view rectangle (expanded by
+/// `BUDDHABROT_MARGIN`) and, for each one that escapes within `limit`
+/// iterations under the Mandelbrot rule, replay its trajectory and record a
+/// hit in `grid` at the pixel corresponding to each intermediate `z`.
+fn accumulate_buddhabrot(
+    grid: &mut HitGrid, pixel_width: usize, pixel_height: usize, upper_left: Complex<f64>,
+    lower_right: Complex<f64>, limit: u32, samples: u32
+) {
+    let margin_re = (lower_right.re - upper_left.re).abs() * BUDDHABROT_MARGIN;
+    let margin_im = (upper_left.im - lower_right.im).abs() * BUDDHABROT_MARGIN;
+    let sample_upper_left = Complex {
+        re: upper_left.re - margin_re, im: upper_left.im + margin_im
+    };
+    let sample_lower_right = Complex {
+        re: lower_right.re + margin_re, im: lower_right.im - margin_im
+    };
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..samples {
+        let c = Complex {
+            re: rng.gen_range(sample_upper_left.re, sample_lower_right.re),
+            im: rng.gen_range(sample_lower_right.im, sample_upper_left.im)
+        };
+
+        if let Some(count) = escape_time(c, limit, FractalKind::Mandelbrot) {
+            let mut z = Complex { re: 0.0, im: 0.0 };
+            for _ in 0..=count {
+                z = fractal_step(z, c, FractalKind::Mandelbrot);
+                if let Some((column, row)) = point_to_pixel(
+                    pixel_width, pixel_height, z, upper_left, lower_right
+                ) {
+                    grid.hit(column, row);
+                }
+            }
         }
     }
 }
 
-/// Write the buffer `pixels`, whose dimensions are given by `pixel_width` & 
+/// Render a Buddhabrot image into a buffer of pixels.
+///
+/// Instead of coloring each pixel by its own escape time, the Buddhabrot
+/// colors each pixel by how often escaping trajectories, sampled from across
+/// the view plus a margin, passed through it. Sampling is split evenly
+/// across threads, each accumulating into its own `HitGrid`, which are then
+/// merged before normalizing to the final image.
+fn buddhabrot(
+    pixel_width: usize, pixel_height: usize, upper_left: Complex<f64>, lower_right: Complex<f64>,
+    limit: u32, samples: u32
+) -> Vec<u8> {
+    let threads = 12;
+    let samples_per_thread = samples / threads as u32 + 1;
+
+    let mut grids: Vec<HitGrid> = (0..threads)
+        .map(|_| HitGrid::new(pixel_width, pixel_height))
+        .collect();
+
+    crossbeam::scope(|spawner| {
+        for grid in grids.iter_mut() {
+            spawner.spawn(move |_| {
+                accumulate_buddhabrot(
+                    grid, pixel_width, pixel_height, upper_left, lower_right, limit,
+                    samples_per_thread
+                );
+            });
+        }
+    }).unwrap();
+
+    let mut merged = HitGrid::new(pixel_width, pixel_height);
+    for grid in &grids {
+        merged.merge(grid);
+    }
+    merged.into_pixels()
+}
+
+/// Write the buffer `pixels`, whose dimensions are given by `pixel_width` &
 /// `pixel_height`, to the file name `filename`.
 fn write_image(filename: &str, pixels: &[u8], pixel_width: usize, pixel_height: usize)
         -> Result<(), std::io::Error> {
+    if filename.ends_with(".ppm") || filename.ends_with(".pnm") {
+        return write_pnm(filename, pixels, pixel_width, pixel_height);
+    }
+
     let output = File::create(filename)?;
 
     let encoder = PNGEncoder::new(output);
     encoder.encode(
-        &pixels,
+        pixels,
         pixel_width as u32,
         pixel_height as u32,
         ColorType::RGB(8)
@@ -166,59 +500,81 @@ fn write_image(filename: &str, pixels: &[u8], pixel_width: usize, pixel_height:
     Ok(())
 }
 
-#[allow(dead_code)]
+/// Write `pixels` out as a binary (P6) PPM: an ASCII header followed by the
+/// raw RGB bytes, with no encoding and no dependency on the `image` crate.
+/// Dependency-light and trivially parseable, at the cost of file size.
+fn write_pnm(filename: &str, pixels: &[u8], pixel_width: usize, pixel_height: usize)
+        -> Result<(), std::io::Error> {
+    let mut output = File::create(filename)?;
+    write!(output, "P6\n{} {}\n255\n", pixel_width, pixel_height)?;
+    output.write_all(pixels)?;
+    Ok(())
+}
+
+#[test]
+fn test_write_pnm() {
+    let path = std::env::temp_dir().join("rust_mandelbrot_test_write_pnm.ppm");
+    let filename = path.to_str().unwrap();
+    let pixels: Vec<u8> = vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 1, 2, 3];
+
+    write_pnm(filename, &pixels, 2, 2).expect("error writing PNM file");
+
+    let contents = std::fs::read(&path).expect("error reading back PNM file");
+    let mut expected = b"P6\n2 2\n255\n".to_vec();
+    expected.extend_from_slice(&pixels);
+    assert_eq!(contents, expected);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[allow(dead_code, clippy::too_many_arguments)]
 fn synchronous(
     pixels: &mut [u8], pixel_width: usize, pixel_height: usize, upper_left: Complex<f64>,
-    lower_right: Complex<f64>
+    lower_right: Complex<f64>, limit: u32, fractal: FractalKind, palette: Palette
 ) {
-    render(pixels, pixel_width, pixel_height, upper_left, lower_right);
+    render(pixels, pixel_width, pixel_height, upper_left, lower_right, limit, fractal, palette);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn concurrent(
     pixels: &mut [u8], pixel_width: usize, pixel_height: usize, upper_left: Complex<f64>,
-    lower_right: Complex<f64>
+    lower_right: Complex<f64>, limit: u32, fractal: FractalKind, palette: Palette
 ) {
-    let threads = 12;
-    let rows_per_band = pixel_height / threads + 1;
-
-    {
-        let bands: Vec<&mut [u8]> = pixels.chunks_mut(rows_per_band * pixel_width * 3)
-                .collect();
-        crossbeam::scope(|spawner| {
-            for (i, band) in bands.into_iter().enumerate() {
-                let top = rows_per_band * i;
-                let band_height = band.len() / (pixel_width * 3);
-                let band_upper_left = pixel_to_point(
-                    pixel_width, pixel_height, (0, top), upper_left, lower_right
-                );
-                let band_lower_right = pixel_to_point(
-                    pixel_width, pixel_height, (pixel_width, top + band_height),
-                    upper_left, lower_right
-                );
+    pixels.par_chunks_mut(pixel_width * 3).enumerate().for_each(|(top, band)| {
+        let band_height = band.len() / (pixel_width * 3);
+        let band_upper_left = pixel_to_point(
+            pixel_width, pixel_height, (0, top), upper_left, lower_right
+        );
+        let band_lower_right = pixel_to_point(
+            pixel_width, pixel_height, (pixel_width, top + band_height), upper_left, lower_right
+        );
 
-                spawner.spawn(move |_| {
-                    render(
-                        band, pixel_width, band_height, band_upper_left, band_lower_right
-                    );
-                });
-            }
-        });
-    }
+        render(
+            band, pixel_width, band_height, band_upper_left, band_lower_right, limit, fractal,
+            palette
+        );
+    });
 }
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() != 5 {
-        writeln!(
-            std::io::stderr(),
-            "Usage: mandelbrot FILE PIXELS UPPERLEFT LOWERRIGHT"
-        ).unwrap();
-        writeln!(
-            std::io::stderr(),
-            "Example: {} mandel.png 1000x750 -1.20,0.35 -1,0.20",
+    if args.len() != 10 {
+        eprintln!(
+            "Usage: mandelbrot FILE PIXELS UPPERLEFT LOWERRIGHT FRACTAL LIMIT PALETTE MODE \
+             SAMPLES"
+        );
+        eprintln!(
+            "Example: {} mandel.png 1000x750 -1.20,0.35 -1,0.20 mandelbrot 255 grayscale \
+             escape_time 0",
             args[0]
-        ).unwrap();
+        );
+        eprintln!("FRACTAL is one of: mandelbrot, multibrot3, burning_ship");
+        eprintln!("PALETTE is one of: grayscale, gradient");
+        eprintln!(
+            "MODE is one of: escape_time, buddhabrot; SAMPLES is the number of points to \
+             sample in buddhabrot mode (ignored otherwise)"
+        );
         std::process::exit(1);
     }
     let (pixel_width, pixel_height) = parse_pair(&args[2], 'x').expect(
@@ -230,10 +586,53 @@ fn main() {
     let lower_right = parse_complex(&args[4]).expect(
         "error parsing lower right corner point"
     );
+    let fractal = FractalKind::from_str(&args[5]).expect(
+        "error parsing fractal kind"
+    );
+    let limit = u32::from_str(&args[6]).expect(
+        "error parsing iteration limit"
+    );
+    let palette = Palette::from_str(&args[7]).expect(
+        "error parsing palette"
+    );
+    let mode = RenderMode::from_str(&args[8]).expect(
+        "error parsing render mode"
+    );
+    let samples = u32::from_str(&args[9]).expect(
+        "error parsing sample count"
+    );
 
-    let mut pixels = vec![0; pixel_width * pixel_height * 3];
-    // synchronous(&mut pixels, pixel_width, pixel_height, upper_left, lower_right);
-    concurrent(&mut pixels, pixel_width, pixel_height, upper_left, lower_right);
+    let pixels = match mode {
+        RenderMode::EscapeTime => {
+            let mut pixels = vec![0; pixel_width * pixel_height * 3];
+            // synchronous(
+            //     &mut pixels, pixel_width, pixel_height, upper_left, lower_right, limit,
+            //     fractal, palette
+            // );
+            concurrent(
+                &mut pixels, pixel_width, pixel_height, upper_left, lower_right, limit, fractal,
+                palette
+            );
+            pixels
+        },
+        RenderMode::Buddhabrot => {
+            if fractal != FractalKind::Mandelbrot {
+                eprintln!(
+                    "warning: buddhabrot mode only supports FractalKind::Mandelbrot; \
+                     ignoring FRACTAL={:?}",
+                    fractal
+                );
+            }
+            if palette != Palette::Grayscale {
+                eprintln!(
+                    "warning: buddhabrot mode always renders in grayscale; ignoring \
+                     PALETTE={:?}",
+                    palette
+                );
+            }
+            buddhabrot(pixel_width, pixel_height, upper_left, lower_right, limit, samples)
+        }
+    };
 
     write_image(&args[1], &pixels, pixel_width, pixel_height).expect(
         "error writing PNG file"